@@ -1,32 +1,352 @@
-use bevy::{input::mouse::AccumulatedMouseMotion, prelude::*, render::{mesh::{VertexAttributeValues, PrimitiveTopology}, render_asset::RenderAssetUsages}};
-use noise::{Fbm, NoiseFn, Perlin};
+use bevy::{
+    image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor},
+    input::mouse::AccumulatedMouseMotion,
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::*,
+    render::{
+        mesh::{MeshVertexAttribute, MeshVertexBufferLayoutRef, PrimitiveTopology, VertexAttributeValues},
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            AsBindGroup, Extent3d, RenderPipelineDescriptor, ShaderRef,
+            SpecializedMeshPipelineError, TextureDimension, TextureFormat, VertexFormat,
+        },
+    },
+};
+use bevy_inspector_egui::{bevy_egui::EguiContexts, quick::ResourceInspectorPlugin};
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
 use rand::Rng;
-
-const RADIUS: f32 = 30.0;
-const NUM_PLATES: usize = 15;
-const PERC_OF_CONTINENTAL_PLATES: f64 = 0.4;
-const CHUNKS_PER_FACE: u32 = 4; // Разделим каждую грань куба на 4x4 чанка (всего 96 чанков)
-const CHUNK_RESOLUTION: u32 = 32; // Разрешение одного чанка (32x32 вершины)
+use serde::{Deserialize, Serialize};
 
 #[derive(Component)]
 struct Globe;
 
+// LOD-тиры чанка по разрешению сетки: чем ближе/прямее к камере, тем выше разрешение.
+const CHUNK_LOD_TIERS: [u32; 4] = [8, 16, 32, 64];
+
+// Чанк помнит свою грань, координаты внутри грани и текущий LOD, чтобы `update_chunk_lod`
+// мог пересобрать именно его меш при смене тира.
 #[derive(Component)]
-struct GlobeChunk;
+struct GlobeChunk {
+    face: Face,
+    chunk_x: u32,
+    chunk_y: u32,
+    lod: u32,
+    // Направление центра чанка на единичной сфере в объектных координатах планеты.
+    center_dir: Vec3,
+}
 
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Reflect, Serialize, Deserialize)]
 enum PlateType {
     Oceanic,
     Continental,
 }
 
+#[derive(Clone, Copy)]
 struct Plate {
     center: Vec3,
     plate_type: PlateType,
     drift_dir: Vec3,
 }
 
+// Настройки одного слоя FBM-шума, как в демо-планете (октавы, затухание, база шероховатости и т.д.)
+#[derive(Reflect, Clone, Copy, Serialize, Deserialize)]
+struct NoiseLayerParams {
+    octaves: usize,
+    persistence: f64,
+    lacunarity: f64,
+    base_roughness: f64,
+    strength: f32,
+    min_value: f32,
+    offset: f32,
+}
+
+impl Default for NoiseLayerParams {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            persistence: 0.5,
+            lacunarity: 2.0,
+            base_roughness: 1.0,
+            strength: 0.35,
+            min_value: -1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+// Все параметры генерации планеты, живущие в одном ресурсе, чтобы их можно было крутить
+// в инспекторе без пересборки проекта.
+#[derive(Resource, Reflect, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+struct PlanetParams {
+    radius: f32,
+    num_plates: usize,
+    perc_of_continental_plates: f64,
+    chunks_per_face: u32,
+    chunk_resolution: u32,
+    warp_strength: f32,
+    edge_threshold: f32,
+    detail_noise: NoiseLayerParams,
+    climate: ClimateParams,
+    mountain_ramp: MountainRampParams,
+}
+
+impl Default for PlanetParams {
+    fn default() -> Self {
+        Self {
+            radius: 30.0,
+            num_plates: 15,
+            perc_of_continental_plates: 0.4,
+            chunks_per_face: 4,
+            chunk_resolution: 32,
+            warp_strength: 0.15,
+            edge_threshold: 0.45,
+            detail_noise: NoiseLayerParams::default(),
+            climate: ClimateParams::default(),
+            mountain_ramp: MountainRampParams::default(),
+        }
+    }
+}
+
+// Точки излома кусочно-линейного рельефа гор: пологие низины до `low_breakpoint`,
+// крутой подъем до `high_breakpoint`, пологое плато выше. Наклоны заданы отдельно для
+// каждого участка, а значения на стыках досчитываются, чтобы профиль оставался непрерывным
+// при любых изменённых точках излома.
+#[derive(Reflect, Clone, Copy, Serialize, Deserialize)]
+struct MountainRampParams {
+    low_breakpoint: f32,
+    high_breakpoint: f32,
+    low_slope: f32,
+    mid_slope: f32,
+    high_slope: f32,
+}
+
+impl Default for MountainRampParams {
+    fn default() -> Self {
+        Self {
+            low_breakpoint: 0.4,
+            high_breakpoint: 0.55,
+            low_slope: 0.5,
+            mid_slope: 4.0,
+            high_slope: 0.4444,
+        }
+    }
+}
+
+// Параметры климатической модели Уиттекера: температура падает с широтой (через |y|)
+// и с высотой (лапс-рейт), влажность берется из отдельного низкочастотного шума.
+#[derive(Reflect, Clone, Copy, Serialize, Deserialize)]
+struct ClimateParams {
+    base_temp: f32,
+    latitude_k: f32,
+    lapse_rate: f32,
+    moisture_frequency: f64,
+}
+
+impl Default for ClimateParams {
+    fn default() -> Self {
+        Self {
+            base_temp: 1.0,
+            latitude_k: 1.0,
+            lapse_rate: 1.5,
+            moisture_frequency: 0.8,
+        }
+    }
+}
+
+// Биомы по Уиттекеру: выбираются из таблицы температура×влажность, кроме
+// океана/пляжа и гор/снега, которые зависят только от высоты.
+#[derive(Clone, Copy)]
+enum Biome {
+    Ocean,
+    Beach,
+    Desert,
+    Grassland,
+    Forest,
+    Jungle,
+    Tundra,
+    Snow,
+    Mountain,
+    Ice,
+}
+
+impl Biome {
+    fn color(self) -> Color {
+        match self {
+            Biome::Ocean => Color::srgb(0.01, 0.1, 0.3),
+            Biome::Beach => Color::srgb(0.85, 0.75, 0.5),
+            Biome::Desert => Color::srgb(0.76, 0.64, 0.36),
+            Biome::Grassland => Color::srgb(0.3, 0.55, 0.2),
+            Biome::Forest => Color::srgb(0.13, 0.4, 0.14),
+            Biome::Jungle => Color::srgb(0.06, 0.3, 0.08),
+            Biome::Tundra => Color::srgb(0.55, 0.55, 0.45),
+            Biome::Snow => Color::srgb(0.95, 0.95, 1.0),
+            Biome::Mountain => Color::srgb(0.4, 0.35, 0.3),
+            Biome::Ice => Color::srgb(0.85, 0.95, 1.0),
+        }
+    }
+
+    // Таблица температура×влажность в духе диаграммы Уиттекера. `temp` и `moisture` уже
+    // нормализованы в 0..1.
+    fn classify(temp: f32, moisture: f32) -> Biome {
+        if temp < 0.15 {
+            return if moisture < 0.5 { Biome::Tundra } else { Biome::Ice };
+        }
+
+        match (temp, moisture) {
+            (t, m) if t < 0.4 && m < 0.3 => Biome::Tundra,
+            (t, m) if t < 0.4 && m < 0.7 => Biome::Grassland,
+            (t, _) if t < 0.4 => Biome::Forest,
+            (t, m) if t < 0.75 && m < 0.3 => Biome::Desert,
+            (t, m) if t < 0.75 && m < 0.6 => Biome::Grassland,
+            (t, _) if t < 0.75 => Biome::Forest,
+            (_, m) if m < 0.3 => Biome::Desert,
+            (_, m) if m < 0.6 => Biome::Grassland,
+            _ => Biome::Jungle,
+        }
+    }
+}
+
+// Отправляется, когда пользователь просит пересоздать планету из текущих `PlanetParams`.
+#[derive(Event, Default)]
+struct RegenerateEvent;
+
+// Плиты и шумы текущей планеты, вынесенные в ресурс, чтобы LOD-система могла пересобирать
+// отдельные чанки в любой момент, не пересчитывая всю планету заново.
+#[derive(Resource)]
+struct PlanetWorld {
+    plates: Vec<Plate>,
+    detail_noise: Fbm<Perlin>,
+    moisture_noise: Fbm<Perlin>,
+    seed: u32,
+}
+
+// Путь, по которому сохраняется/загружается планета.
+const SAVE_PATH: &str = "planet.ron";
+
+// Сериализуемый снимок планеты: сид шума, список плит, все `PlanetParams` и накопленный
+// тектоникой рельеф — без последнего сохранение/загрузка молча откатывали бы симуляцию
+// к нулю, даже если пользователь до этого долго проигрывал/шагал по ней.
+#[derive(Serialize, Deserialize)]
+struct PlanetSave {
+    seed: u32,
+    plates: Vec<PlateSave>,
+    params: PlanetParams,
+    height_field: HeightField,
+}
+
+// `Plate` хранит `Vec3`, который не реализует (De)Serialize в этой версии bevy — храним
+// компоненты как массивы и конвертируем при сохранении/загрузке.
+#[derive(Serialize, Deserialize)]
+struct PlateSave {
+    center: [f32; 3],
+    plate_type: PlateType,
+    drift_dir: [f32; 3],
+}
+
+impl From<&Plate> for PlateSave {
+    fn from(plate: &Plate) -> Self {
+        Self {
+            center: plate.center.to_array(),
+            plate_type: plate.plate_type,
+            drift_dir: plate.drift_dir.to_array(),
+        }
+    }
+}
+
+impl From<&PlateSave> for Plate {
+    fn from(save: &PlateSave) -> Self {
+        Self {
+            center: Vec3::from_array(save.center),
+            plate_type: save.plate_type,
+            drift_dir: Vec3::from_array(save.drift_dir),
+        }
+    }
+}
+
+// Запрашивают сохранение/загрузку планеты по кнопкам в UI; обрабатываются отдельными
+// системами, как и `RegenerateEvent`.
+#[derive(Event, Default)]
+struct SaveRequestEvent;
+
+#[derive(Event, Default)]
+struct LoadRequestEvent;
+
+// Разрешение сетки широта/долгота, в которой копится рельеф от тектоники. Не привязано
+// к разрешению чанков, поэтому переживает смену LOD-тира и пересборку мешей.
+const HEIGHT_FIELD_LATS: usize = 64;
+const HEIGHT_FIELD_LONS: usize = 128;
+
+// Персистентный буфер накопленной тектонической деформации (подъем на конвергентных
+// границах, рифтинг на дивергентных), индексированный по направлению на единичной сфере.
+#[derive(Clone, Serialize, Deserialize)]
+struct HeightField {
+    cells: Vec<f32>,
+}
+
+impl HeightField {
+    fn new() -> Self {
+        Self { cells: vec![0.0; HEIGHT_FIELD_LATS * HEIGHT_FIELD_LONS] }
+    }
+
+    fn cell_coords(dir: Vec3) -> (usize, usize) {
+        let lat = dir.y.clamp(-1.0, 1.0).asin();
+        let lon = dir.z.atan2(dir.x);
+        let v = (((lat / std::f32::consts::PI) + 0.5) * HEIGHT_FIELD_LATS as f32) as usize;
+        let u = (((lon / (2.0 * std::f32::consts::PI)) + 0.5) * HEIGHT_FIELD_LONS as f32) as usize;
+        (v.min(HEIGHT_FIELD_LATS - 1), u.min(HEIGHT_FIELD_LONS - 1))
+    }
+
+    fn cell_dir(v: usize, u: usize) -> Vec3 {
+        let lat = ((v as f32 + 0.5) / HEIGHT_FIELD_LATS as f32 - 0.5) * std::f32::consts::PI;
+        let lon = ((u as f32 + 0.5) / HEIGHT_FIELD_LONS as f32 - 0.5) * 2.0 * std::f32::consts::PI;
+        Vec3::new(lat.cos() * lon.cos(), lat.sin(), lat.cos() * lon.sin())
+    }
+
+    fn accumulate(&mut self, dir: Vec3, delta: f32) {
+        let (v, u) = Self::cell_coords(dir);
+        self.cells[v * HEIGHT_FIELD_LONS + u] += delta;
+    }
+
+    fn sample(&self, dir: Vec3) -> f32 {
+        let (v, u) = Self::cell_coords(dir);
+        self.cells[v * HEIGHT_FIELD_LONS + u]
+    }
+}
+
+// Состояние пошаговой симуляции тектоники: фиксированный шаг по времени, плюс
+// play/pause/step управление из UI.
+#[derive(Resource)]
+struct TectonicSim {
+    running: bool,
+    step_requested: bool,
+    accumulator: f32,
+    step_dt: f32,
+    drift_speed: f32,
+    uplift_rate: f32,
+    rift_rate: f32,
+    boundary_threshold: f32,
+    height_field: HeightField,
+}
+
+impl Default for TectonicSim {
+    fn default() -> Self {
+        Self {
+            running: false,
+            step_requested: false,
+            accumulator: 0.0,
+            step_dt: 0.1,
+            drift_speed: 0.05,
+            uplift_rate: 0.02,
+            rift_rate: 0.015,
+            boundary_threshold: 0.12,
+            height_field: HeightField::new(),
+        }
+    }
+}
+
 // Направления граней куба
+#[derive(Clone, Copy)]
 enum Face { Front, Back, Left, Right, Up, Down }
 
 impl Face {
@@ -46,35 +366,268 @@ impl Face {
     }
 }
 
+// Высота вершины после тектоники/шума (используется материалом, чтобы выбирать
+// песок/траву/скалу/снег без повторного вычисления в шейдере).
+const ATTRIBUTE_TERRAIN_HEIGHT: MeshVertexAttribute =
+    MeshVertexAttribute::new("TerrainHeight", 988_540_917, VertexFormat::Float32);
+// Уклон вершины: 0 у полюса нормали (плоско), 1 там, где нормаль перпендикулярна радиусу
+// (отвесный склон) — используется для подмешивания скалы на крутых участках.
+const ATTRIBUTE_TERRAIN_SLOPE: MeshVertexAttribute =
+    MeshVertexAttribute::new("TerrainSlope", 988_540_918, VertexFormat::Float32);
+
+// Трипланарный материал, смешивающий тайлы песка/травы/скалы/снега из текстурного массива
+// по высоте и уклону вершины вместо плоской заливки `ATTRIBUTE_COLOR`. Биомный цвет из
+// `apply_tectonic_deformation` остается как множитель поверх тайлов, чтобы климатические
+// полосы (тундра/пустыня/джунгли) все еще были видны.
+#[derive(Asset, AsBindGroup, TypePath, Clone)]
+struct TerrainMaterial {
+    #[uniform(0)]
+    height_bands: Vec4,
+    #[texture(1, dimension = "2d_array")]
+    #[sampler(2)]
+    terrain_tiles: Handle<Image>,
+}
+
+impl Material for TerrainMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/terrain.wgsl".into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        "shaders/terrain.wgsl".into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_NORMAL.at_shader_location(1),
+            Mesh::ATTRIBUTE_COLOR.at_shader_location(2),
+            ATTRIBUTE_TERRAIN_HEIGHT.at_shader_location(3),
+            ATTRIBUTE_TERRAIN_SLOPE.at_shader_location(4),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
+
+// Handle на единственный экземпляр `TerrainMaterial`, который делят все чанки планеты.
+#[derive(Resource)]
+struct TerrainMaterialHandle(Handle<TerrainMaterial>);
+
+// Заводит материал один раз при старте. У нас нет упакованного KTX2-массива тайлов в
+// репозитории (и Cargo-фич `ktx2`/`ruzstd` для его загрузки), поэтому вместо
+// `asset_server.load` собираем маленький процедурный массив-заглушку прямо здесь —
+// иначе загрузка молча зависала бы/проваливалась и вся трипланарная раскладка
+// рендерилась бы плейсхолдером bevy по умолчанию.
+fn setup_terrain_material(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+) {
+    let terrain_tiles = images.add(build_placeholder_terrain_tiles());
+    let handle = materials.add(TerrainMaterial {
+        // x..w = границы пляж/равнина, равнина/скала, скала/снег (совпадают с биомной раскраской).
+        height_bands: Vec4::new(0.035, 0.18, 0.4, 0.6),
+        terrain_tiles,
+    });
+    commands.insert_resource(TerrainMaterialHandle(handle));
+}
+
+// Массив из 4 однотонных тайлов (песок/трава/скала/снег), уложенных друг над другом
+// и переинтерпретированных как слои текстурного массива — держит трипланарный
+// материал рабочим до тех пор, пока в репозиторий не попадет настоящий KTX2-атлас.
+fn build_placeholder_terrain_tiles() -> Image {
+    const TILE: u32 = 4;
+    const LAYERS: u32 = 4;
+    const COLORS: [[u8; 4]; LAYERS as usize] = [
+        [194, 178, 128, 255], // песок
+        [80, 140, 55, 255],   // трава
+        [110, 100, 90, 255],  // скала
+        [235, 240, 245, 255], // снег
+    ];
+
+    let mut data = Vec::with_capacity((TILE * TILE * LAYERS * 4) as usize);
+    for color in COLORS {
+        for _ in 0..(TILE * TILE) {
+            data.extend_from_slice(&color);
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d { width: TILE, height: TILE * LAYERS, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.reinterpret_stacked_2d_as_array(LAYERS);
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..default()
+    });
+    image
+}
+
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_systems(Startup, setup_globe)
-        .add_systems(Update, rotate_globe)
+        .add_plugins(MaterialPlugin::<TerrainMaterial>::default())
+        .add_plugins(ResourceInspectorPlugin::<PlanetParams>::default())
+        .init_resource::<PlanetParams>()
+        .register_type::<PlanetParams>()
+        .init_resource::<TectonicSim>()
+        .add_event::<RegenerateEvent>()
+        .add_event::<SaveRequestEvent>()
+        .add_event::<LoadRequestEvent>()
+        .add_systems(Startup, (setup_terrain_material, setup_globe).chain())
+        .add_systems(Update, (rotate_globe, regenerate_button_ui, regenerate_globe, update_chunk_lod, tectonic_sim_step, save_planet, load_planet))
         .run();
 }
 
 fn setup_globe(
+    commands: Commands,
+    meshes: ResMut<Assets<Mesh>>,
+    material_handle: Res<TerrainMaterialHandle>,
+    params: Res<PlanetParams>,
+    sim: Res<TectonicSim>,
+) {
+    spawn_globe(commands, meshes, material_handle, &params, &sim.height_field);
+}
+
+// Пересобирает планету с нуля: сносит старые чанки и запускает цикл чанков `setup_globe`
+// заново с актуальными `PlanetParams`.
+fn regenerate_globe(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    meshes: ResMut<Assets<Mesh>>,
+    material_handle: Res<TerrainMaterialHandle>,
+    params: Res<PlanetParams>,
+    mut sim: ResMut<TectonicSim>,
+    mut events: EventReader<RegenerateEvent>,
+    globes: Query<Entity, With<Globe>>,
 ) {
-    let mut rng = rand::rng();
+    if events.read().next().is_none() {
+        return;
+    }
+
+    for globe in &globes {
+        commands.entity(globe).despawn();
+    }
+
+    // Новая планета — новые плиты, так что накопленный рельеф старой больше не имеет смысла.
+    sim.height_field = HeightField::new();
+
+    spawn_globe(commands, meshes, material_handle, &params, &sim.height_field);
+}
+
+// Пишет сид, плиты, параметры и накопленный тектоникой рельеф текущей планеты в
+// `planet.ron` в формате RON.
+fn save_planet(
+    mut events: EventReader<SaveRequestEvent>,
+    params: Res<PlanetParams>,
+    sim: Res<TectonicSim>,
+    world: Option<Res<PlanetWorld>>,
+) {
+    if events.read().next().is_none() {
+        return;
+    }
+    let Some(world) = world else { return };
+
+    let save = PlanetSave {
+        seed: world.seed,
+        plates: world.plates.iter().map(PlateSave::from).collect(),
+        params: params.clone(),
+        height_field: sim.height_field.clone(),
+    };
+
+    match ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default()) {
+        Ok(ron) => {
+            if let Err(err) = std::fs::write(SAVE_PATH, ron) {
+                error!("Failed to write {SAVE_PATH}: {err}");
+            }
+        }
+        Err(err) => error!("Failed to serialize planet: {err}"),
+    }
+}
 
-    let plates = generate_plates(&mut rng);
+// Читает `planet.ron`, заменяет `PlanetParams` и пересобирает планету из сохраненных
+// сида, плит и рельефа — детерминированно, без новой случайной генерации и без потери
+// накопленной тектонической деформации.
+fn load_planet(
+    mut commands: Commands,
+    meshes: ResMut<Assets<Mesh>>,
+    material_handle: Res<TerrainMaterialHandle>,
+    mut params: ResMut<PlanetParams>,
+    mut sim: ResMut<TectonicSim>,
+    mut events: EventReader<LoadRequestEvent>,
+    globes: Query<Entity, With<Globe>>,
+) {
+    if events.read().next().is_none() {
+        return;
+    }
 
+    let contents = match std::fs::read_to_string(SAVE_PATH) {
+        Ok(contents) => contents,
+        Err(err) => {
+            error!("Failed to read {SAVE_PATH}: {err}");
+            return;
+        }
+    };
+    let save: PlanetSave = match ron::from_str(&contents) {
+        Ok(save) => save,
+        Err(err) => {
+            error!("Failed to parse {SAVE_PATH}: {err}");
+            return;
+        }
+    };
+
+    *params = save.params;
+    sim.height_field = save.height_field;
+
+    for globe in &globes {
+        commands.entity(globe).despawn();
+    }
+
+    let plates = save.plates.iter().map(Plate::from).collect();
+    spawn_globe_with(commands, meshes, material_handle, &params, &sim.height_field, plates, save.seed);
+}
+
+fn spawn_globe(
+    commands: Commands,
+    meshes: ResMut<Assets<Mesh>>,
+    material_handle: Res<TerrainMaterialHandle>,
+    params: &PlanetParams,
+    tectonic: &HeightField,
+) {
+    let mut rng = rand::rng();
+    let plates = generate_plates(&mut rng, params);
     let seed = rng.random_range(0..=u32::MAX);
-    let perlin = Fbm::<Perlin>::new(seed);
 
+    spawn_globe_with(commands, meshes, material_handle, params, tectonic, plates, seed);
+}
 
-    let material_handle = materials.add(StandardMaterial {
-        base_color: Color::WHITE,
-        ..default()
-    });
+// Собирает планету из уже готовых плит и сида — общий путь и для случайной генерации,
+// и для загрузки сохраненного `PlanetSave`.
+fn spawn_globe_with(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    material_handle: Res<TerrainMaterialHandle>,
+    params: &PlanetParams,
+    tectonic: &HeightField,
+    plates: Vec<Plate>,
+    seed: u32,
+) {
+    let perlin = build_fbm(seed, &params.detail_noise);
+    let moisture_noise = Fbm::<Perlin>::new(seed.wrapping_add(1));
 
     commands.spawn((
-        Globe, 
-        Transform::IDENTITY, 
+        Globe,
+        Transform::IDENTITY,
         Visibility::default(),
         InheritedVisibility::default(),
     ))
@@ -82,22 +635,37 @@ fn setup_globe(
         // 2. Проходим по всем 6 граням куба
         for face in Face::all() {
             // 3. Каждую грань делим на сетку чанков
-            for y in 0..CHUNKS_PER_FACE {
-                for x in 0..CHUNKS_PER_FACE {
-                    
+            for y in 0..params.chunks_per_face {
+                for x in 0..params.chunks_per_face {
+                    // Стартуем на среднем тире, а не сразу на полном разрешении —
+                    // `update_chunk_lod` поднимет/опустит его на первом кадре.
+                    let lod = params.chunk_resolution;
+
                     // Создаем меш для конкретного чанка
                     let mut mesh = create_chunk_mesh(
-                        &face, 
-                        x, y, 
-                        CHUNKS_PER_FACE, 
-                        CHUNK_RESOLUTION
+                        &face,
+                        x, y,
+                        params.chunks_per_face,
+                        lod,
+                        params.radius,
                     );
-                    
-                    apply_tectonic_deformation(&mut mesh, &plates, &perlin);
+
+                    apply_tectonic_deformation(&mut mesh, &plates, &perlin, &moisture_noise, tectonic, params);
+                    // Чанки стартуют все на одном и том же LOD-тире, так что без этого
+                    // `update_chunk_lod` не сочтет "изменившимся" тир тех чанков, чей целевой
+                    // тир совпадает со стартовым, и не наложит им швы — их граница осталась
+                    // бы видна, пока камера не сдвинется.
+                    apply_chunk_skirts(&mut mesh, lod, params.radius, APPROX_RELIEF_RANGE * SKIRT_DEPTH_FRACTION);
                     parent.spawn((
-                        GlobeChunk,
+                        GlobeChunk {
+                            face,
+                            chunk_x: x,
+                            chunk_y: y,
+                            lod,
+                            center_dir: chunk_center_dir(&face, x, y, params.chunks_per_face),
+                        },
                         Mesh3d(meshes.add(mesh)),
-                        MeshMaterial3d(material_handle.clone()),
+                        MeshMaterial3d(material_handle.0.clone()),
                     ));
                 }
             }
@@ -110,15 +678,209 @@ fn setup_globe(
             shadows_enabled: true,
             ..default()
         },
-        Transform::from_xyz(RADIUS * 3.0, RADIUS * 3.0, RADIUS * 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Transform::from_xyz(params.radius * 3.0, params.radius * 3.0, params.radius * 3.0).looking_at(Vec3::ZERO, Vec3::Y),
     ));
-    commands.spawn((Camera3d::default(), Transform::from_xyz(0.0, 0.0, RADIUS * 3.0)));
+    commands.spawn((Camera3d::default(), Transform::from_xyz(0.0, 0.0, params.radius * 3.0)));
+
+    commands.insert_resource(PlanetWorld { plates, detail_noise: perlin, moisture_noise, seed });
+}
+
+// Направление центра чанка на кубе, спроецированное на сферу — используется как его
+// приблизительная позиция для LOD-замеров, без полного прохода по вершинам меша.
+fn chunk_center_dir(face: &Face, chunk_x: u32, chunk_y: u32, chunks_per_face: u32) -> Vec3 {
+    let (origin, right, up) = face.get_vectors();
+    let local_x = (chunk_x as f32 + 0.5) / chunks_per_face as f32;
+    let local_y = (chunk_y as f32 + 0.5) / chunks_per_face as f32;
+    let p = origin + (local_x * 2.0 - 1.0) * right + (local_y * 2.0 - 1.0) * up;
+    cube_to_sphere(p)
+}
+
+// Выбирает тир разрешения по углу между направлением на чанк и направлением на камеру:
+// чанки, смотрящие прямо на камеру, получают самую густую сетку.
+fn pick_lod_tier(angle: f32) -> u32 {
+    if angle < 0.5 {
+        CHUNK_LOD_TIERS[3]
+    } else if angle < 1.0 {
+        CHUNK_LOD_TIERS[2]
+    } else if angle < 1.5 {
+        CHUNK_LOD_TIERS[1]
+    } else {
+        CHUNK_LOD_TIERS[0]
+    }
+}
+
+// Раз в кадр проверяет каждый чанк и, если его целевой тир сменился, перестраивает меш
+// целиком на новом разрешении (новая сетка + тектоника + швы на границах).
+fn update_chunk_lod(
+    mut meshes: ResMut<Assets<Mesh>>,
+    world: Option<Res<PlanetWorld>>,
+    sim: Res<TectonicSim>,
+    params: Res<PlanetParams>,
+    camera_q: Query<&Transform, With<Camera3d>>,
+    globe_q: Query<&Transform, With<Globe>>,
+    mut chunks_q: Query<(&mut GlobeChunk, &mut Mesh3d)>,
+) {
+    let Some(world) = world else { return };
+    let Ok(camera_transform) = camera_q.single() else { return };
+    let Ok(globe_transform) = globe_q.single() else { return };
+
+    let camera_dir = camera_transform.translation.normalize();
+
+    for (mut chunk, mut mesh3d) in &mut chunks_q {
+        let world_center = globe_transform.rotation * chunk.center_dir;
+        let angle = world_center.angle_between(camera_dir);
+        let tier = pick_lod_tier(angle);
+
+        if tier == chunk.lod {
+            continue;
+        }
+        chunk.lod = tier;
+
+        let mut mesh = create_chunk_mesh(&chunk.face, chunk.chunk_x, chunk.chunk_y, params.chunks_per_face, tier, params.radius);
+        apply_tectonic_deformation(&mut mesh, &world.plates, &world.detail_noise, &world.moisture_noise, &sim.height_field, &params);
+        apply_chunk_skirts(&mut mesh, tier, params.radius, APPROX_RELIEF_RANGE * SKIRT_DEPTH_FRACTION);
+        mesh3d.0 = meshes.add(mesh);
+    }
+}
+
+// Примерный суммарный перепад высот рельефа в `apply_tectonic_deformation` (от дна
+// желобов ~-0.9 до снежных шапок ~0.8) — используется только чтобы задать глубину шва
+// в долях от реального масштаба террейна, а не абсолютным числом, которое раньше никак
+// не было привязано к этому масштабу.
+const APPROX_RELIEF_RANGE: f32 = 1.7;
+// Доля `APPROX_RELIEF_RANGE`, на которую утапливается край чанка — должна быть
+// достаточной, чтобы скрыть щель между соседними LOD-тирами, но малой по сравнению
+// с самим рельефом, иначе шов читается как видимая канава.
+const SKIRT_DEPTH_FRACTION: f32 = 0.02;
+
+// Подтягивает вершины по краям чанка немного внутрь сферы, чтобы стык с соседним чанком
+// другого LOD-тира не давал видимую щель.
+fn apply_chunk_skirts(mesh: &mut Mesh, res: u32, radius: f32, skirt_depth: f32) {
+    if let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+    {
+        for y in 0..=res {
+            for x in 0..=res {
+                let is_border = x == 0 || x == res || y == 0 || y == res;
+                if !is_border {
+                    continue;
+                }
+                let i = (y * (res + 1) + x) as usize;
+                let p = Vec3::from(positions[i]);
+                let dir = p.normalize();
+                let height = p.length() - radius;
+                positions[i] = (dir * (radius + height - skirt_depth)).to_array();
+            }
+        }
+    }
+}
+
+// Двигает тектонику фиксированным шагом (если запущена или запрошен один шаг) и, если
+// хоть один шаг прошел, перестраивает все чанки, чтобы новый рельеф стал виден.
+fn tectonic_sim_step(
+    time: Res<Time>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut sim: ResMut<TectonicSim>,
+    world: Option<ResMut<PlanetWorld>>,
+    params: Res<PlanetParams>,
+    mut chunks_q: Query<(&GlobeChunk, &mut Mesh3d)>,
+) {
+    let Some(mut world) = world else { return };
+    if !sim.running && !sim.step_requested {
+        return;
+    }
+
+    let mut stepped = false;
+    sim.accumulator += time.delta_secs();
+    while sim.step_requested || sim.accumulator >= sim.step_dt {
+        if sim.step_requested {
+            sim.step_requested = false;
+        } else {
+            sim.accumulator -= sim.step_dt;
+        }
+        step_tectonics(
+            &mut world.plates,
+            &mut sim.height_field,
+            sim.drift_speed,
+            sim.uplift_rate,
+            sim.rift_rate,
+            sim.boundary_threshold,
+            sim.step_dt,
+        );
+        stepped = true;
+        if !sim.running {
+            break;
+        }
+    }
+
+    if !stepped {
+        return;
+    }
+
+    for (chunk, mut mesh3d) in &mut chunks_q {
+        let mut mesh = create_chunk_mesh(&chunk.face, chunk.chunk_x, chunk.chunk_y, params.chunks_per_face, chunk.lod, params.radius);
+        apply_tectonic_deformation(&mut mesh, &world.plates, &world.detail_noise, &world.moisture_noise, &sim.height_field, &params);
+        apply_chunk_skirts(&mut mesh, chunk.lod, params.radius, APPROX_RELIEF_RANGE * SKIRT_DEPTH_FRACTION);
+        mesh3d.0 = meshes.add(mesh);
+    }
+}
+
+// Небольшая egui-кнопка поверх инспектора: по нажатию шлём `RegenerateEvent` вместо
+// автоматической пересборки на каждое изменение слайдера.
+fn regenerate_button_ui(
+    mut contexts: EguiContexts,
+    mut events: EventWriter<RegenerateEvent>,
+    mut save_events: EventWriter<SaveRequestEvent>,
+    mut load_events: EventWriter<LoadRequestEvent>,
+    mut sim: ResMut<TectonicSim>,
+) {
+    let Ok(ctx) = contexts.ctx_mut() else { return };
+    bevy_inspector_egui::egui::Window::new("Planet").show(ctx, |ui| {
+        if ui.button("Regenerate").clicked() {
+            events.write(RegenerateEvent);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Save").clicked() {
+                save_events.write(SaveRequestEvent);
+            }
+            if ui.button("Load").clicked() {
+                load_events.write(LoadRequestEvent);
+            }
+        });
+
+        ui.separator();
+        ui.label("Tectonics");
+        ui.horizontal(|ui| {
+            let play_label = if sim.running { "Pause" } else { "Play" };
+            if ui.button(play_label).clicked() {
+                sim.running = !sim.running;
+            }
+            if ui.button("Step").clicked() {
+                sim.step_requested = true;
+            }
+        });
+    });
+}
+
+fn build_fbm(seed: u32, noise_params: &NoiseLayerParams) -> Fbm<Perlin> {
+    Fbm::<Perlin>::new(seed)
+        .set_octaves(noise_params.octaves)
+        .set_persistence(noise_params.persistence)
+        .set_lacunarity(noise_params.lacunarity)
+        .set_frequency(noise_params.base_roughness)
 }
 
-fn generate_plates(rng: &mut impl Rng) -> Vec<Plate> {
-    let mut plates = Vec::with_capacity(NUM_PLATES as usize);
-    for _ in 0..NUM_PLATES {
-        let plate_type = if rng.random_bool(PERC_OF_CONTINENTAL_PLATES) {
+fn generate_plates(rng: &mut impl Rng, params: &PlanetParams) -> Vec<Plate> {
+    // Значения приходят прямо из инспектора и могут оказаться вне допустимого диапазона
+    // (ползунок не клампит сам) — `random_bool` паникует вне 0..=1, а пустой `Vec` плит
+    // роняет `step_tectonics`/`apply_tectonic_deformation` при первом же обращении.
+    let num_plates = params.num_plates.max(2);
+    let perc_of_continental_plates = params.perc_of_continental_plates.clamp(0.0, 1.0);
+
+    let mut plates = Vec::with_capacity(num_plates);
+    for _ in 0..num_plates {
+        let plate_type = if rng.random_bool(perc_of_continental_plates) {
             PlateType::Continental
         } else {
             PlateType::Oceanic
@@ -142,7 +904,111 @@ fn generate_plates(rng: &mut impl Rng) -> Vec<Plate> {
     plates
 }
 
-fn create_chunk_mesh(face: &Face, chunk_x: u32, chunk_y: u32, chunks_per_face: u32, res: u32) -> Mesh {
+// Находит две ближайшие к `v` плиты и расстояние до границы между ними (разница
+// расстояний до второй и до первой) — та же метрика, что использует `apply_tectonic_deformation`.
+fn find_nearest_two_plates(plates: &[Plate], v: Vec3) -> (usize, usize, f32) {
+    let mut dist_1 = f32::MAX;
+    let mut dist_2 = f32::MAX;
+    let mut p1_idx = 0;
+    let mut p2_idx = 0;
+
+    for (i, plate) in plates.iter().enumerate() {
+        let d = v.distance(plate.center);
+        if d < dist_1 {
+            dist_2 = dist_1;
+            p2_idx = p1_idx;
+            dist_1 = d;
+            p1_idx = i;
+        } else if d < dist_2 {
+            dist_2 = d;
+            p2_idx = i;
+        }
+    }
+
+    (p1_idx, p2_idx, dist_2 - dist_1)
+}
+
+// Один фиксированный шаг тектонической симуляции: плиты дрейфуют вдоль своего
+// `drift_dir` по большому кругу, а на границах по знаку относительной скорости
+// копится подъем (конвергенция) или рифтинг (дивергенция) в `height_field`.
+#[allow(clippy::too_many_arguments)]
+fn step_tectonics(
+    plates: &mut [Plate],
+    height_field: &mut HeightField,
+    drift_speed: f32,
+    uplift_rate: f32,
+    rift_rate: f32,
+    boundary_threshold: f32,
+    dt: f32,
+) {
+    for plate in plates.iter_mut() {
+        let axis = plate.center.cross(plate.drift_dir);
+        if axis.length_squared() < 1e-6 {
+            continue;
+        }
+        let rotation = Quat::from_axis_angle(axis.normalize(), drift_speed * dt);
+        plate.center = (rotation * plate.center).normalize();
+    }
+
+    for v_i in 0..HEIGHT_FIELD_LATS {
+        for u_i in 0..HEIGHT_FIELD_LONS {
+            let dir = HeightField::cell_dir(v_i, u_i);
+            let (p1_idx, p2_idx, boundary_dist) = find_nearest_two_plates(plates, dir);
+            if boundary_dist > boundary_threshold {
+                continue;
+            }
+
+            let p1 = &plates[p1_idx];
+            let p2 = &plates[p2_idx];
+            let boundary_normal = (p2.center - p1.center).normalize_or_zero();
+            if boundary_normal == Vec3::ZERO {
+                continue;
+            }
+
+            // Знак относительной скорости вдоль нормали к границе: >0 — плиты сходятся,
+            // <0 — расходятся, около 0 — трансформный разлом (без вертикальной деформации).
+            let regime = (p1.drift_dir - p2.drift_dir).dot(boundary_normal);
+            let f = (1.0 - boundary_dist / boundary_threshold).clamp(0.0, 1.0);
+
+            if regime > 0.05 {
+                height_field.accumulate(dir, uplift_rate * f * dt);
+            } else if regime < -0.05 {
+                height_field.accumulate(dir, -rift_rate * f * dt);
+            }
+        }
+    }
+}
+
+// Кусочно-линейная кривая для положительной (надводной) части высоты: пологие низины,
+// резкий обрыв/предгорья, а затем высокое плато — вместо равномерно округлых гор от
+// линейного шума. Значения на стыках участков досчитываются из наклонов, чтобы сдвиг
+// точек излома в инспекторе не создавал разрыв.
+fn apply_mountain_ramp(h: f32, ramp: &MountainRampParams) -> f32 {
+    let t = h * 2.0;
+    let low_value = ramp.low_slope * ramp.low_breakpoint;
+
+    if t < ramp.low_breakpoint {
+        ramp.low_slope * t
+    } else if t < ramp.high_breakpoint {
+        ramp.mid_slope * (t - ramp.low_breakpoint) + low_value
+    } else {
+        let mid_value = ramp.mid_slope * (ramp.high_breakpoint - ramp.low_breakpoint) + low_value;
+        ramp.high_slope * (t - ramp.high_breakpoint) + mid_value
+    }
+}
+
+// Аналитическая равноплощадная развертка куба на сферу (см. Snyder / cube-to-sphere
+// "area preserving" mapping). Обычный `normalize()` сгущает вершины у 8 углов куба и
+// растягивает их у центров граней; эта формула распределяет их намного равномернее.
+fn cube_to_sphere(p: Vec3) -> Vec3 {
+    let (x, y, z) = (p.x, p.y, p.z);
+    let sx = x * (1.0 - y * y / 2.0 - z * z / 2.0 + y * y * z * z / 3.0).max(0.0).sqrt();
+    let sy = y * (1.0 - z * z / 2.0 - x * x / 2.0 + z * z * x * x / 3.0).max(0.0).sqrt();
+    let sz = z * (1.0 - x * x / 2.0 - y * y / 2.0 + x * x * y * y / 3.0).max(0.0).sqrt();
+    Vec3::new(sx, sy, sz).normalize()
+}
+
+fn create_chunk_mesh(face: &Face, chunk_x: u32, chunk_y: u32, chunks_per_face: u32, res: u32, radius: f32) -> Mesh {
     let mut positions = Vec::new();
     let mut indices = Vec::new();
     let (origin, right, up) = face.get_vectors();
@@ -155,9 +1021,9 @@ fn create_chunk_mesh(face: &Face, chunk_x: u32, chunk_y: u32, chunks_per_face: u
 
             // Точка на грани куба
             let p = origin + (local_x * 2.0 - 1.0) * right + (local_y * 2.0 - 1.0) * up;
-            
-            // Проекция на сферу
-            positions.push(p.normalize() * RADIUS);
+
+            // Равноплощадная проекция на сферу вместо простого normalize()
+            positions.push(cube_to_sphere(p) * radius);
 
             // Индексы для треугольников (стандартная сетка)
             if x < res && y < res {
@@ -173,11 +1039,21 @@ fn create_chunk_mesh(face: &Face, chunk_x: u32, chunk_y: u32, chunks_per_face: u
         .with_inserted_indices(bevy::render::mesh::Indices::U32(indices))
 }
 
-fn apply_tectonic_deformation(mesh: &mut Mesh, plates: &[Plate], noise: &impl NoiseFn<f64, 3>) {
+fn apply_tectonic_deformation(
+    mesh: &mut Mesh,
+    plates: &[Plate],
+    noise: &impl NoiseFn<f64, 3>,
+    moisture_noise: &impl NoiseFn<f64, 3>,
+    tectonic: &HeightField,
+    params: &PlanetParams,
+) {
     if let Some(VertexAttributeValues::Float32x3(positions)) =
         mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
     {
         let mut new_colors = Vec::with_capacity(positions.len());
+        let mut new_heights = Vec::with_capacity(positions.len());
+        let warp_strength = params.warp_strength;
+        let edge_threshold = params.edge_threshold;
 
         for pos in positions.iter_mut() {
             let v = Vec3::from(*pos).normalize();
@@ -185,7 +1061,6 @@ fn apply_tectonic_deformation(mesh: &mut Mesh, plates: &[Plate], noise: &impl No
             // --- 1. ИСКАЖЕНИЕ ГРАНИЦ (Domain Warping) ---
             // Мы добавляем шум к позиции ПЕРЕД поиском ближайшей плиты.
             // Это сделает границы "рваными" и скругленными.
-            let warp_strength = 0.15;
             let warp_noise = Vec3::new(
                 noise.get([v.x as f64 * 1.5, v.y as f64 * 1.5, v.z as f64 * 1.5]) as f32,
                 noise.get([v.y as f64 * 1.5, v.z as f64 * 1.5, v.x as f64 * 1.5]) as f32,
@@ -216,7 +1091,6 @@ fn apply_tectonic_deformation(mesh: &mut Mesh, plates: &[Plate], noise: &impl No
             let p1 = &plates[p1_idx];
             let p2 = &plates[p2_idx];
             let boundary_dist = dist_2 - dist_1;
-            let edge_threshold = 0.45;
 
             // --- 3. БАЗОВАЯ ВЫСОТА И ПЛЯЖИ ---
             let mut h = if p1.plate_type == PlateType::Continental {
@@ -290,31 +1164,91 @@ fn apply_tectonic_deformation(mesh: &mut Mesh, plates: &[Plate], noise: &impl No
             }
 
             // --- 5. ФИНАЛЬНЫЙ ШУМ И КЛЕМПЫ ---
-            let detail_noise =
-                noise.get([v.x as f64 * 4.0, v.y as f64 * 4.0, v.z as f64 * 4.0]) as f32;
-            h += detail_noise * 0.35;
+            // Слой детального шума теперь читает параметры (strength/min_value/offset) из
+            // ресурса, а не захардкожен, как octaves/persistence выше в `build_fbm`.
+            let detail = &params.detail_noise;
+            let detail_noise = (noise.get([v.x as f64 * 4.0, v.y as f64 * 4.0, v.z as f64 * 4.0])
+                as f32
+                * detail.strength)
+                .max(detail.min_value)
+                + detail.offset;
+            h += detail_noise;
+
+            // Накопленный по шагам симуляции рельеф (подъем/рифтинг) добавляется поверх
+            // статической геологии вместо того, чтобы пересчитываться с нуля каждый раз.
+            h += tectonic.sample(v);
+
+            // Нелинейный рельеф: низины остаются пологими, а дальше идет резкий подъем и
+            // плато, вместо равномерно "округлых" гор от линейного шума.
+            if h > 0.0 {
+                h = apply_mountain_ramp(h, &params.mountain_ramp);
+            }
 
             let final_h = h.max(-0.9);
             let visual_h = final_h;
             // let visual_h = if final_h < 0.0 { 0.0 } else { final_h };
-            *pos = (v * (RADIUS + visual_h)).to_array();
-
-            let color = match final_h {
-                x if x <= -0.45 => Color::srgb(0.0, 0.03, 0.12), // Глубокие желоба
-                x if x <= -0.18 => Color::srgb(0.01, 0.1, 0.3),  // Океан
-                x if x < 0.0 => Color::srgb(0.05, 0.25, 0.5),    // Мелководье
-                x if x < 0.035 => Color::srgb(0.85, 0.75, 0.5),  // Пляж (Песок)
-                x if x < 0.18 => Color::srgb(0.2, 0.45, 0.15),   // Равнина (Зелень)
-                x if x < 0.4 => Color::srgb(0.4, 0.35, 0.3),     // Горы
-                x if x < 0.6 => Color::srgb(0.3, 0.25, 0.2),     // Высокие скалы
-                _ => Color::srgb(0.95, 0.95, 1.0),               // Снег
+            *pos = (v * (params.radius + visual_h)).to_array();
+
+            // Высота все еще решает океан/пляж/горы-по-высоте; всё, что между пляжем и
+            // горами, раскрашивается по климатической таблице температура×влажность.
+            let color = if final_h <= -0.45 {
+                Color::srgb(0.0, 0.03, 0.12) // Глубокие желоба
+            } else if final_h <= -0.18 {
+                Biome::Ocean.color()
+            } else if final_h < 0.0 {
+                Color::srgb(0.05, 0.25, 0.5) // Мелководье
+            } else if final_h < 0.035 {
+                Biome::Beach.color()
+            } else if final_h >= 0.6 {
+                Biome::Snow.color()
+            } else if final_h >= 0.4 {
+                Biome::Mountain.color()
+            } else {
+                let climate = &params.climate;
+                let temperature = (climate.base_temp - climate.latitude_k * v.y.abs()
+                    - climate.lapse_rate * final_h.max(0.0))
+                .clamp(0.0, 1.0);
+                let moisture = ((moisture_noise.get([
+                    v.x as f64 * climate.moisture_frequency,
+                    v.y as f64 * climate.moisture_frequency,
+                    v.z as f64 * climate.moisture_frequency,
+                ]) as f32
+                    + 1.0)
+                    * 0.5)
+                    .clamp(0.0, 1.0);
+                Biome::classify(temperature, moisture).color()
             };
             new_colors.push(color.to_linear().to_f32_array());
+            new_heights.push(final_h);
         }
 
         mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, new_colors);
+        mesh.insert_attribute(ATTRIBUTE_TERRAIN_HEIGHT, new_heights);
     }
     mesh.compute_smooth_normals();
+    insert_terrain_slope(mesh);
+}
+
+// Уклон = 1 - dot(normal, radial_dir): 0 на пологих вершинах, 1 на отвесных склонах.
+// Считается отдельным проходом, потому что нормали доступны только после
+// `compute_smooth_normals`.
+fn insert_terrain_slope(mesh: &mut Mesh) {
+    let (Some(VertexAttributeValues::Float32x3(positions)), Some(VertexAttributeValues::Float32x3(normals))) =
+        (mesh.attribute(Mesh::ATTRIBUTE_POSITION), mesh.attribute(Mesh::ATTRIBUTE_NORMAL))
+    else {
+        return;
+    };
+
+    let slopes: Vec<f32> = positions
+        .iter()
+        .zip(normals.iter())
+        .map(|(pos, normal)| {
+            let radial_dir = Vec3::from(*pos).normalize();
+            (1.0 - Vec3::from(*normal).dot(radial_dir)).clamp(0.0, 1.0)
+        })
+        .collect();
+
+    mesh.insert_attribute(ATTRIBUTE_TERRAIN_SLOPE, slopes);
 }
 
 fn rotate_globe(